@@ -0,0 +1,50 @@
+use std::thread;
+use std::time::Duration;
+
+use gilrs::{Axis, Button, EventType, Gilrs};
+use gtk4::glib;
+
+/// A driving-mode input event, forwarded to the GTK main loop from whichever
+/// thread noticed it (gilrs polls off the GTK loop, so its events have to
+/// cross a [glib::MainContext::channel] before touching widget state).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum InputEvent {
+    /// Horizontal axis in `-1.0..=1.0`, from a d-pad or the left stick.
+    Axis(f64),
+    /// A face button was pressed, requesting the click animation.
+    ButtonPressed,
+}
+
+/// Poll gilrs on a background thread and forward its events to the GTK main
+/// loop. Returns the receiving end of the channel events are sent on.
+pub(crate) fn spawn_gamepad_listener() -> Option<glib::Receiver<InputEvent>> {
+    let mut gilrs = Gilrs::new().ok()?;
+
+    let (sender, receiver) = glib::MainContext::channel(glib::Priority::default());
+
+    thread::spawn(move || loop {
+        while let Some(event) = gilrs.next_event() {
+            let translated = match event.event {
+                EventType::AxisChanged(Axis::LeftStickX, value, _) => {
+                    Some(InputEvent::Axis(value as f64))
+                }
+                EventType::ButtonPressed(Button::South, _) => Some(InputEvent::ButtonPressed),
+                EventType::ButtonPressed(Button::DPadLeft, _) => Some(InputEvent::Axis(-1.0)),
+                EventType::ButtonPressed(Button::DPadRight, _) => Some(InputEvent::Axis(1.0)),
+                EventType::ButtonReleased(Button::DPadLeft, _)
+                | EventType::ButtonReleased(Button::DPadRight, _) => Some(InputEvent::Axis(0.0)),
+                _ => None,
+            };
+
+            if let Some(event) = translated {
+                if sender.send(event).is_err() {
+                    return;
+                }
+            }
+        }
+
+        thread::sleep(Duration::from_millis(16));
+    });
+
+    Some(receiver)
+}
@@ -1,10 +1,9 @@
-use std::path::PathBuf;
+use std::cell::Cell;
 use std::rc::Rc;
-use std::str::FromStr;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use gdk4::prelude::PaintableExt;
 use gif::GifPaintable;
@@ -14,12 +13,12 @@ use gtk4::prelude::{ApplicationExt, ApplicationExtManual};
 use gtk4::prelude::{GtkWindowExt, WidgetExt};
 use gtk4::Fixed;
 use gtk4::Picture;
-use gtk4::{ApplicationWindow, GestureClick};
-use gtk4_layer_shell::{Edge, Layer, LayerShell};
+use gtk4::{ApplicationWindow, EventControllerKey, GestureClick};
+use gtk4_layer_shell::{Edge, KeyboardMode, Layer, LayerShell};
 
 use helpers::load_css;
-use helpers::screen_resolution;
 use helpers::update_input_region;
+use input::InputEvent;
 use state::State;
 
 use crate::config::Config;
@@ -27,7 +26,24 @@ use crate::error::BuddyError;
 
 mod gif;
 mod helpers;
+mod input;
+mod monitor;
+mod socket;
 mod state;
+mod vfs;
+
+/// Velocity below which the character is considered to have come to rest and
+/// snaps back into an idle/running state instead of bouncing forever.
+const VELOCITY_EPSILON: f64 = 1.0;
+
+/// How close to a `goto` destination counts as arrived.
+const GOTO_ARRIVAL_EPSILON: f64 = 4.0;
+
+/// Fixed physics step, independent of how often the timer actually fires.
+const FIXED_TIMESTEP: Duration = Duration::from_millis(16);
+/// Upper bound on the elapsed time folded into the accumulator in one go, so
+/// a stalled/suspended process doesn't replay a huge backlog of steps at once.
+const MAX_FRAME_TIME: Duration = Duration::from_millis(250);
 
 /// Prepare and render character.
 pub(crate) fn render_character(config: Config, sprites_path: String) {
@@ -66,7 +82,10 @@ fn activate(
         .map_err(BuddyError::from)?;
 
     let Config {
-        movement_speed,
+        // now consumed as pixels/second rather than pixels/tick now that
+        // movement runs on a fixed timestep instead of the timer's own rate;
+        // renamed locally so call sites can't confuse it with the old unit
+        movement_speed: movement_speed_pps,
         onclick_event_chance,
         x,
         y,
@@ -74,6 +93,11 @@ fn activate(
         debug,
         signal_frequency,
         automatic_reload,
+        gravity,
+        restitution,
+        jump_speed,
+        bounce_edges,
+        input_mode,
         ..
     } = config;
 
@@ -87,23 +111,39 @@ fn activate(
     for (anchor, state) in [
         (Edge::Left, true),
         (Edge::Right, true),
-        (Edge::Top, false),
+        (Edge::Top, true),
         (Edge::Bottom, true),
     ] {
         window.set_anchor(anchor, state);
     }
 
+    let placement = monitor::resolve(&window.display(), &config)?;
+
+    if let Some(monitor) = &placement.monitor {
+        window.set_monitor(monitor);
+    }
+
     window.present(); // present prematurely to be able to get screen resolution
 
-    let (screen_width, screen_height) =
-        screen_resolution(&window).ok_or(BuddyError::NoScreenResolution)?;
+    let (screen_x, screen_y, screen_width, screen_height) = (
+        placement.bounds.x,
+        placement.bounds.y,
+        placement.bounds.width,
+        placement.bounds.height,
+    );
+    let sprite_source = Rc::new(vfs::SpriteSource::resolve(sprites_path.as_str()));
     let sprites = GifPaintable::default();
-    sprites.load_animations(PathBuf::from_str(sprites_path.as_str()).unwrap(), &config)?;
+    sprites.load_animations(&sprite_source, &config)?;
 
     let (width, height) = helpers::infer_size(&config, sprites.intrinsic_aspect_ratio());
 
-    // check for valid starting coordinates
-    if !debug && ((x + width) >= screen_width || x < 0 || (y + height) >= screen_height || y < 0) {
+    // check for valid starting coordinates, within the resolved monitor/span bounds
+    if !debug
+        && ((x + width) >= screen_x + screen_width
+            || x < screen_x
+            || (y + height) >= screen_y + screen_height
+            || y < screen_y)
+    {
         return Err(BuddyError::CoordinatesOutOfBounds(
             x,
             y,
@@ -124,25 +164,33 @@ fn activate(
     character.set_size_request(width, height);
 
     let fixed = Fixed::new();
-    fixed.put(&character, x as f64, y as f64);
+    // `Fixed` child coordinates are window-local, while `x`/`y` (like every
+    // other coordinate in this function) are in the monitor/span's global
+    // space, so translate by the chosen monitor's origin before placing it
+    fixed.put(&character, (x - screen_x) as f64, (y - screen_y) as f64);
     window.set_child(Some(&fixed));
-    window.set_size_request(screen_width, height);
+    window.set_size_request(screen_width, screen_height);
     window.set_resizable(false);
 
     // default input region
-    update_input_region(&window, width, height, x, 0);
+    update_input_region(&window, width, height, x - screen_x, y - screen_y);
+
+    // socket commands pause/resume movement and override its speed; shared
+    // with the control socket handler below
+    let paused = Rc::new(Cell::new(false));
+    let horizontal_speed = Rc::new(Cell::new(movement_speed_pps as f64));
 
     let sprites_clone = sprites.clone();
-    let sprites_path_clone = Rc::clone(sprites_path);
+    let sprite_source_clone = Rc::clone(&sprite_source);
+    let config_for_reload = config.copy_primitive();
 
     timeout_add_local(
         Duration::from_millis(1000 / signal_frequency as u64),
         move || {
             if automatic_reload || reload_sprites.swap(false, Ordering::Relaxed) {
-                if let Err(err) = sprites_clone.load_animations(
-                    PathBuf::from_str(sprites_path_clone.as_str()).unwrap(),
-                    &config,
-                ) {
+                if let Err(err) =
+                    sprites_clone.load_animations(&sprite_source_clone, &config_for_reload)
+                {
                     println!("Warning: Could not update sprites: {}", err)
                 }
             }
@@ -150,50 +198,304 @@ fn activate(
         },
     );
 
+    // (vx, vy) in pixels/second, shared between the movement tick and the
+    // click gesture so a click can inject a jump impulse.
+    let velocity: Rc<Cell<(f64, f64)>> = Rc::new(Cell::new((0.0, 0.0)));
+    // `goto` sets a horizontal destination the movement tick walks towards;
+    // `teleport` moves there instantly instead and clears any pending goto.
+    let goto_target: Rc<Cell<Option<f64>>> = Rc::new(Cell::new(None));
+
+    // external control socket: a background thread parses commands and
+    // forwards them here through a channel, since GTK widgets aren't `Send`
+    let receiver = socket::spawn(socket::socket_path())?;
+
+    let character_for_socket = character.clone();
+    let sprites_for_socket = sprites.clone();
+    let fixed_for_socket = fixed.clone();
+    let window_for_socket = window.clone();
+    let paused_for_socket = Rc::clone(&paused);
+    let horizontal_speed_for_socket = Rc::clone(&horizontal_speed);
+    let sprite_source_for_socket = Rc::clone(&sprite_source);
+    let goto_target_for_socket = Rc::clone(&goto_target);
+    let config_for_socket = config.copy_primitive();
+
+    receiver.attach(None, move |request| {
+        let socket::Request { command, reply } = request;
+
+        let check_bounds = |cmd_x: i32, cmd_y: i32| {
+            if !debug
+                && ((cmd_x + width) >= screen_x + screen_width
+                    || cmd_x < screen_x
+                    || (cmd_y + height) >= screen_y + screen_height
+                    || cmd_y < screen_y)
+            {
+                Err(format!(
+                    "position {},{} is out of bounds ({},{} .. {},{})",
+                    cmd_x,
+                    cmd_y,
+                    screen_x,
+                    screen_y,
+                    screen_x + screen_width,
+                    screen_y + screen_height
+                ))
+            } else {
+                Ok(())
+            }
+        };
+
+        let outcome = match command {
+            socket::Command::Anim(state) => {
+                sprites_for_socket.switch_animation(state);
+                Ok(())
+            }
+            socket::Command::Goto(cmd_x, cmd_y) => check_bounds(cmd_x, cmd_y).and_then(|()| {
+                // `goto` only drives horizontal movement — gravity owns `y` —
+                // so reject a `y` that isn't the ground rather than silently
+                // dropping it; `teleport` is the way to place the character
+                // somewhere that isn't standing on the floor
+                let floor = (screen_y + screen_height - height) as f64;
+                if (cmd_y as f64 - floor).abs() > GOTO_ARRIVAL_EPSILON {
+                    // `check_bounds` above rejects the floor itself (its bottom
+                    // edge is exclusive), so name the bottom-most y that passes both
+                    return Err(format!(
+                        "goto only walks along the ground (y={}); use teleport to place the character elsewhere",
+                        floor as i32 - 1
+                    ));
+                }
+
+                // walked towards by the movement tick rather than applied instantly
+                goto_target_for_socket.set(Some(cmd_x as f64));
+                Ok(())
+            }),
+            socket::Command::Teleport(cmd_x, cmd_y) => check_bounds(cmd_x, cmd_y).map(|()| {
+                goto_target_for_socket.set(None);
+                // `cmd_x`/`cmd_y` are global like every other coordinate here;
+                // translate to window-local before touching `Fixed`/the input region
+                fixed_for_socket.move_(
+                    &character_for_socket,
+                    (cmd_x - screen_x) as f64,
+                    (cmd_y - screen_y) as f64,
+                );
+                update_input_region(
+                    &window_for_socket,
+                    width,
+                    height,
+                    cmd_x - screen_x,
+                    cmd_y - screen_y,
+                );
+            }),
+            socket::Command::Pause => {
+                paused_for_socket.set(true);
+                Ok(())
+            }
+            socket::Command::Resume => {
+                paused_for_socket.set(false);
+                Ok(())
+            }
+            socket::Command::Speed(px_per_sec) => {
+                horizontal_speed_for_socket.set(px_per_sec);
+                Ok(())
+            }
+            socket::Command::Reload => sprites_for_socket
+                .load_animations(&sprite_source_for_socket, &config_for_socket)
+                .map_err(|err| err.to_string()),
+        };
+
+        let _ = reply.send(outcome);
+
+        ControlFlow::from(true)
+    });
+
+    // driving mode: horizontal axis in -1.0..=1.0 set by gamepad or keyboard,
+    // overriding the automatic traversal direction while held
+    let driving_axis: Rc<Cell<f64>> = Rc::new(Cell::new(0.0));
+
+    if input_mode {
+        // the overlay doesn't receive key events at all until the layer
+        // surface asks the compositor for keyboard focus
+        window.set_keyboard_mode(KeyboardMode::OnDemand);
+
+        if let Some(receiver) = input::spawn_gamepad_listener() {
+            let sprites_for_input = sprites.clone();
+            let driving_axis_for_gamepad = Rc::clone(&driving_axis);
+
+            receiver.attach(None, move |event| {
+                match event {
+                    InputEvent::Axis(axis) => driving_axis_for_gamepad.set(axis),
+                    InputEvent::ButtonPressed => {
+                        if sprites_for_input.state() != State::Click {
+                            sprites_for_input.switch_animation(State::Click);
+                        }
+                    }
+                }
+                ControlFlow::from(true)
+            });
+        }
+
+        let key_controller = EventControllerKey::new();
+        let driving_axis_for_keys = Rc::clone(&driving_axis);
+
+        key_controller.connect_key_pressed(move |_controller, key, _code, _state| {
+            match key {
+                gtk4::gdk::Key::Left | gtk4::gdk::Key::a => driving_axis_for_keys.set(-1.0),
+                gtk4::gdk::Key::Right | gtk4::gdk::Key::d => driving_axis_for_keys.set(1.0),
+                _ => {}
+            }
+            gtk4::glib::Propagation::Proceed
+        });
+
+        let driving_axis_for_keys = Rc::clone(&driving_axis);
+        key_controller.connect_key_released(move |_controller, key, _code, _state| match key {
+            gtk4::gdk::Key::Left
+            | gtk4::gdk::Key::a
+            | gtk4::gdk::Key::Right
+            | gtk4::gdk::Key::d => driving_axis_for_keys.set(0.0),
+            _ => {}
+        });
+
+        window.add_controller(key_controller);
+    }
+
     let character_clone = character.clone();
     let sprites_clone = sprites.clone();
-    // move character
-    timeout_add_local(
-        Duration::from_millis(1000 / movement_speed as u64),
-        move || {
-            if sprites_clone.state() == State::Running {
-                let (x, y) = fixed.child_position(&character_clone);
-                // update position
-                let (x, y) = if left {
-                    let x = if x - 10.0 <= -width as f64 {
-                        screen_width as f64
-                    } else {
-                        x - 10.0
-                    };
+    let velocity_clone = Rc::clone(&velocity);
+    let goto_target_clone = Rc::clone(&goto_target);
+    let mut last_tick = Instant::now();
+    let mut accumulator = Duration::ZERO;
+    // move character, on a fixed-timestep accumulator so simulation speed
+    // stays consistent regardless of how often this timer actually fires
+    timeout_add_local(FIXED_TIMESTEP, move || {
+        if paused.get() {
+            last_tick = Instant::now();
+            return ControlFlow::from(true);
+        }
 
-                    (x, y)
+        let now = Instant::now();
+        accumulator += (now - last_tick).min(MAX_FRAME_TIME);
+        last_tick = now;
+
+        while accumulator >= FIXED_TIMESTEP {
+            let dt = FIXED_TIMESTEP.as_secs_f64();
+            // `Fixed` child coordinates are window-local; translate to the
+            // monitor/span's global space so the physics below matches every
+            // other coordinate in this function (bounds, edges, `goto`)
+            let (local_x, local_y) = fixed.child_position(&character_clone);
+            let (x, y) = (local_x + screen_x as f64, local_y + screen_y as f64);
+            let (mut vx, mut vy) = velocity_clone.get();
+
+            let axis = if input_mode { driving_axis.get() } else { 0.0 };
+
+            if let Some(target_x) = goto_target_clone.get() {
+                // `goto` walks towards its destination instead of snapping to it
+                let distance = target_x - x;
+                if distance.abs() <= GOTO_ARRIVAL_EPSILON {
+                    vx = 0.0;
+                    goto_target_clone.set(None);
+                    if !matches!(sprites_clone.state(), State::Falling | State::Jumping) {
+                        sprites_clone.switch_animation(State::Idle);
+                    }
                 } else {
-                    let x = if x + 10.0 >= screen_width as f64 {
-                        -width as f64
-                    } else {
-                        x + 10.0
-                    };
-
-                    (x, y)
-                };
-                // move along screen
-                fixed.move_(&character_clone, x, y);
-                update_input_region(&window, width, height, x as i32, 0);
+                    vx = distance.signum() * horizontal_speed.get();
+                    if !matches!(sprites_clone.state(), State::Falling | State::Jumping) {
+                        sprites_clone.switch_animation(State::Running);
+                    }
+                }
+            } else if axis != 0.0 {
+                // driving mode overrides the automatic traversal direction
+                vx = axis * horizontal_speed.get();
+                if !matches!(sprites_clone.state(), State::Falling | State::Jumping) {
+                    sprites_clone.switch_animation(State::Running);
+                }
+            } else if input_mode && sprites_clone.state() == State::Running {
+                // input released: come to a stop instead of resuming auto-traversal
+                vx = 0.0;
+                sprites_clone.switch_animation(State::Idle);
+            } else if sprites_clone.state() == State::Running {
+                // automatic horizontal traversal only drives the character while
+                // it is running; falling/jumping keeps whatever vx it launched with
+                let speed = horizontal_speed.get();
+                vx = if left { -speed } else { speed };
             }
-            ControlFlow::from(true)
-        },
-    );
 
-    // change state of character (idle/initiating run)
+            vy += gravity * dt;
+
+            let mut x = x + vx * dt;
+            let mut y = y + vy * dt;
+
+            // floor collision: clamp and bounce with damping, in global coordinates
+            let floor = (screen_y + screen_height - height) as f64;
+            if y >= floor {
+                y = floor;
+
+                if vy.abs() < VELOCITY_EPSILON {
+                    // resting contact: the incoming velocity is too small to
+                    // count as a bounce, so settle instead of reflecting it
+                    // (reflecting first and thresholding the result never
+                    // actually reaches zero, it just keeps halving forever)
+                    vy = 0.0;
+                    if matches!(sprites_clone.state(), State::Falling | State::Jumping) {
+                        sprites_clone.switch_animation(if vx == 0.0 {
+                            State::Idle
+                        } else {
+                            State::Running
+                        });
+                    }
+                } else {
+                    vy = -vy * restitution;
+                    sprites_clone.switch_animation(State::Jumping);
+                }
+            } else if vy < 0.0 {
+                sprites_clone.switch_animation(State::Jumping);
+            } else if vy > 0.0 {
+                sprites_clone.switch_animation(State::Falling);
+            }
+
+            // horizontal edges: wrap around or bounce back, depending on config.
+            // the wrap direction follows the character's actual travel
+            // direction (vx), not the static `left` config, since driving
+            // mode or a `goto` can send it either way regardless of `left`
+            if x + width as f64 <= screen_x as f64 || x >= (screen_x + screen_width) as f64 {
+                if bounce_edges {
+                    vx = -vx * restitution;
+                    x = x.clamp(screen_x as f64, (screen_x + screen_width - width) as f64);
+                } else if vx <= 0.0 {
+                    x = (screen_x + screen_width) as f64;
+                } else {
+                    x = (screen_x - width) as f64;
+                }
+            }
+
+            velocity_clone.set((vx, vy));
+            // translate back to window-local before touching `Fixed`/the input region
+            let (local_x, local_y) = (x - screen_x as f64, y - screen_y as f64);
+            fixed.move_(&character_clone, local_x, local_y);
+            update_input_region(&window, width, height, local_x as i32, local_y as i32);
+
+            accumulator -= FIXED_TIMESTEP;
+        }
+
+        ControlFlow::from(true)
+    });
+
+    // change state of character (idle/initiating run/jump)
     let gesture = GestureClick::new();
 
     gesture.connect_pressed(
         move |_gesture: &GestureClick, _n_press: i32, _x: f64, _y: f64| {
             let state = sprites.state();
             if state != State::Click {
-                if state == State::Idle && fastrand::u8(0..=100) <= onclick_event_chance {
-                    // play click event and continue
-                    sprites.switch_animation(State::Click);
+                if matches!(state, State::Idle | State::Running)
+                    && fastrand::u8(0..=100) <= onclick_event_chance
+                {
+                    if fastrand::bool() {
+                        // play click event and continue
+                        sprites.switch_animation(State::Click);
+                    } else {
+                        // hop: inject an upward impulse, gravity takes it from there
+                        let (vx, _) = velocity.get();
+                        velocity.set((vx, -jump_speed));
+                        sprites.switch_animation(State::Jumping);
+                    }
                 } else {
                     sprites.switch_animation(!state);
                 }
@@ -0,0 +1,116 @@
+use gdk4::prelude::{DisplayExt, ListModelExt, MonitorExt};
+use gdk4::{Display, Monitor};
+use gio::ListModel;
+
+use crate::config::Config;
+use crate::error::BuddyError;
+
+/// Placement bounds the character is confined to: either one physical
+/// output, or the union of every output in "span" mode.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Bounds {
+    pub(crate) x: i32,
+    pub(crate) y: i32,
+    pub(crate) width: i32,
+    pub(crate) height: i32,
+}
+
+/// The monitor the window should be pinned to, and the bounds the movement
+/// logic should treat as the screen.
+pub(crate) struct Placement {
+    /// `None` in "span" mode, where the character isn't pinned to one output.
+    pub(crate) monitor: Option<Monitor>,
+    pub(crate) bounds: Bounds,
+}
+
+/// Resolve [Config::monitor]/[Config::span_monitors] against the outputs
+/// `display` currently reports.
+pub(crate) fn resolve(display: &Display, config: &Config) -> Result<Placement, BuddyError> {
+    let monitors = display.monitors();
+    let count = monitors.n_items();
+
+    if count == 0 {
+        return Err(BuddyError::NoScreenResolution);
+    }
+
+    if config.span_monitors {
+        let mut bounds: Option<Bounds> = None;
+
+        for index in 0..count {
+            let monitor = monitors
+                .item(index)
+                .and_downcast::<Monitor>()
+                .ok_or(BuddyError::NoScreenResolution)?;
+            let geometry = monitor.geometry();
+
+            bounds = Some(match bounds {
+                None => Bounds {
+                    x: geometry.x(),
+                    y: geometry.y(),
+                    width: geometry.width(),
+                    height: geometry.height(),
+                },
+                Some(current) => union(current, geometry.x(), geometry.y(), geometry.width(), geometry.height()),
+            });
+        }
+
+        return Ok(Placement {
+            monitor: None,
+            bounds: bounds.ok_or(BuddyError::NoScreenResolution)?,
+        });
+    }
+
+    let monitor = select(&monitors, count, config.monitor.as_deref())?;
+    let geometry = monitor.geometry();
+
+    Ok(Placement {
+        bounds: Bounds {
+            x: geometry.x(),
+            y: geometry.y(),
+            width: geometry.width(),
+            height: geometry.height(),
+        },
+        monitor: Some(monitor),
+    })
+}
+
+/// Pick a monitor by connector name (e.g. `"DP-1"`) or index; defaults to
+/// the first reported monitor when unset.
+fn select(monitors: &ListModel, count: u32, selector: Option<&str>) -> Result<Monitor, BuddyError> {
+    for index in 0..count {
+        let monitor = monitors
+            .item(index)
+            .and_downcast::<Monitor>()
+            .ok_or(BuddyError::NoScreenResolution)?;
+
+        let matches = match selector {
+            Some(selector) => {
+                selector.parse::<u32>().map(|i| i == index).unwrap_or(false)
+                    || monitor.connector().as_deref() == Some(selector)
+            }
+            None => index == 0,
+        };
+
+        if matches {
+            return Ok(monitor);
+        }
+    }
+
+    Err(BuddyError::MonitorNotFound(
+        selector.map(str::to_string).unwrap_or_default(),
+    ))
+}
+
+fn union(a: Bounds, x: i32, y: i32, width: i32, height: i32) -> Bounds {
+    let left = a.x.min(x);
+    let top = a.y.min(y);
+    let right = (a.x + a.width).max(x + width);
+    let bottom = (a.y + a.height).max(y + height);
+
+    Bounds {
+        x: left,
+        y: top,
+        width: right - left,
+        height: bottom - top,
+    }
+}
@@ -0,0 +1,143 @@
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+
+use gtk4::glib;
+
+use crate::error::BuddyError;
+
+use super::state::State;
+
+/// A command parsed off the control socket, forwarded to the GTK main loop.
+///
+/// GTK widgets aren't `Send`, so the listener thread can't mutate them
+/// directly — it ships one of these across a [glib::MainContext::channel]
+/// instead.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Command {
+    Anim(State),
+    Goto(i32, i32),
+    Teleport(i32, i32),
+    Pause,
+    Resume,
+    Speed(f64),
+    Reload,
+}
+
+/// A parsed [Command] paired with a way to report back whether it actually
+/// succeeded once the main loop executes it, so the socket client doesn't
+/// get `ok` for a command that was rejected (e.g. out-of-bounds coordinates).
+pub(crate) struct Request {
+    pub(crate) command: Command,
+    pub(crate) reply: mpsc::Sender<Result<(), String>>,
+}
+
+/// Per-instance control socket path, `$XDG_RUNTIME_DIR/buddy-<pid>.sock`.
+pub(crate) fn socket_path() -> PathBuf {
+    let runtime_dir = std::env::var("XDG_RUNTIME_DIR").unwrap_or_else(|_| "/tmp".to_string());
+
+    PathBuf::from(runtime_dir).join(format!("buddy-{}.sock", std::process::id()))
+}
+
+/// Bind `path` and spawn a reader thread accepting line-oriented commands on
+/// it, returning the receiving end of the channel [Request]s are forwarded
+/// on. The main loop must call [Request::reply] for each one so the client
+/// learns whether its command actually succeeded.
+pub(crate) fn spawn(path: PathBuf) -> Result<glib::Receiver<Request>, BuddyError> {
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).map_err(BuddyError::from)?;
+
+    let (sender, receiver) = glib::MainContext::channel(glib::Priority::default());
+
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            handle_connection(stream, &sender);
+        }
+    });
+
+    Ok(receiver)
+}
+
+/// Read commands off one connection until the peer disconnects, replying
+/// with `ok`/`error: ...` on the same stream once the main loop has actually
+/// executed each one.
+fn handle_connection(stream: UnixStream, sender: &glib::Sender<Request>) {
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(_) => return,
+    };
+    let reader = BufReader::new(stream);
+
+    for line in reader.lines().map_while(Result::ok) {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match parse(&line) {
+            Ok(command) => {
+                let (reply_tx, reply_rx) = mpsc::channel();
+                let outcome = if sender.send(Request { command, reply: reply_tx }).is_ok() {
+                    reply_rx
+                        .recv()
+                        .unwrap_or_else(|_| Err("buddy is shutting down".to_string()))
+                } else {
+                    Err("buddy is shutting down".to_string())
+                };
+
+                let line = match outcome {
+                    Ok(()) => "ok\n".to_string(),
+                    Err(message) => format!("error: {}\n", message),
+                };
+                let _ = writer.write_all(line.as_bytes());
+            }
+            Err(message) => {
+                let _ = writer.write_all(format!("error: {}\n", message).as_bytes());
+            }
+        }
+    }
+}
+
+/// Parse a single line of the control protocol, e.g. `goto 120 40`.
+fn parse(line: &str) -> Result<Command, String> {
+    let mut parts = line.split_whitespace();
+    let command = parts.next().ok_or("empty command")?;
+
+    match command {
+        "anim" => match parts.next() {
+            Some("idle") => Ok(Command::Anim(State::Idle)),
+            Some("run") => Ok(Command::Anim(State::Running)),
+            Some("click") => Ok(Command::Anim(State::Click)),
+            Some(other) => Err(format!("unknown animation state: {}", other)),
+            None => Err("anim requires a state".to_string()),
+        },
+        "goto" => parse_coordinates(parts).map(|(x, y)| Command::Goto(x, y)),
+        "teleport" => parse_coordinates(parts).map(|(x, y)| Command::Teleport(x, y)),
+        "pause" => Ok(Command::Pause),
+        "resume" => Ok(Command::Resume),
+        "speed" => parts
+            .next()
+            .ok_or_else(|| "speed requires a value".to_string())?
+            .parse::<f64>()
+            .map(Command::Speed)
+            .map_err(|_| "speed must be a number".to_string()),
+        "reload" => Ok(Command::Reload),
+        other => Err(format!("unknown command: {}", other)),
+    }
+}
+
+fn parse_coordinates<'a>(mut parts: impl Iterator<Item = &'a str>) -> Result<(i32, i32), String> {
+    let x = parts
+        .next()
+        .ok_or("missing x coordinate")?
+        .parse::<i32>()
+        .map_err(|_| "x must be an integer".to_string())?;
+    let y = parts
+        .next()
+        .ok_or("missing y coordinate")?
+        .parse::<i32>()
+        .map_err(|_| "y must be an integer".to_string())?;
+
+    Ok((x, y))
+}
@@ -0,0 +1,57 @@
+use std::fs::File;
+use std::io::{Cursor, Read, Seek};
+use std::path::PathBuf;
+
+use zip::ZipArchive;
+
+use crate::error::BuddyError;
+
+/// Something animations can be read from regardless of backing store: a
+/// plain [File] or an in-memory buffer decompressed out of an archive.
+pub(crate) trait SpriteRead: Read + Seek {}
+impl<T: Read + Seek> SpriteRead for T {}
+
+/// Where per-state sprite GIFs (`idle.gif`, `run.gif`, `click.gif`) are read
+/// from: a directory of loose files, or a single packaged archive.
+#[derive(Debug, Clone)]
+pub(crate) enum SpriteSource {
+    Directory(PathBuf),
+    Archive(PathBuf),
+}
+
+impl SpriteSource {
+    /// Resolve `path` into a [SpriteSource]: a `.zip` file is treated as an
+    /// archive, anything else as a directory of loose sprites.
+    pub(crate) fn resolve(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("zip") => SpriteSource::Archive(path),
+            _ => SpriteSource::Directory(path),
+        }
+    }
+
+    /// Open the sprite entry `name` (e.g. `"idle.gif"`) for reading.
+    pub(crate) fn open(&self, name: &str) -> Result<Box<dyn SpriteRead>, BuddyError> {
+        match self {
+            SpriteSource::Directory(dir) => {
+                let file = File::open(dir.join(name))
+                    .map_err(|_| BuddyError::MissingSpriteEntry(name.to_string()))?;
+                Ok(Box::new(file))
+            }
+            SpriteSource::Archive(archive_path) => {
+                let file = File::open(archive_path).map_err(BuddyError::from)?;
+                let mut archive = ZipArchive::new(file)
+                    .map_err(|_| BuddyError::BadSpriteArchive(archive_path.clone()))?;
+                let mut entry = archive
+                    .by_name(name)
+                    .map_err(|_| BuddyError::MissingSpriteEntry(name.to_string()))?;
+
+                let mut buffer = Vec::new();
+                entry.read_to_end(&mut buffer).map_err(BuddyError::from)?;
+
+                Ok(Box::new(Cursor::new(buffer)))
+            }
+        }
+    }
+}